@@ -0,0 +1,140 @@
+use clap::ValueEnum;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// What to do with each group of confirmed duplicates.
+#[derive(Copy, Clone, Debug, ValueEnum, PartialEq, Eq)]
+pub enum Action {
+    /// Only report the duplicate groups; no file is touched (the default).
+    Report,
+    /// Remove every file in a group except the kept one.
+    Delete,
+    /// Replace every file in a group except the kept one with a hard link.
+    Hardlink,
+    /// Replace every file in a group except the kept one with a symlink.
+    Symlink,
+}
+
+/// Which file in a duplicate group to keep.
+#[derive(Copy, Clone, Debug, ValueEnum, PartialEq, Eq)]
+pub enum KeepPolicy {
+    /// Keep whichever file happens to be first in the group.
+    First,
+    /// Keep the file with the most recent modification time.
+    Newest,
+    /// Keep the file with the oldest modification time.
+    Oldest,
+    /// Keep the file with the shortest path.
+    ShortestPath,
+}
+
+impl KeepPolicy {
+    /// Pick the index of the file to keep within `group`, defaulting to 0
+    /// (the `first` policy, and the fallback when metadata can't be read).
+    pub fn pick(&self, group: &[PathBuf]) -> usize {
+        match self {
+            KeepPolicy::First => 0,
+            KeepPolicy::ShortestPath => group
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, p)| p.as_os_str().len())
+                .map(|(i, _)| i)
+                .unwrap_or(0),
+            KeepPolicy::Newest => pick_by_mtime(group, |a, b| b.cmp(a)),
+            KeepPolicy::Oldest => pick_by_mtime(group, |a, b| a.cmp(b)),
+        }
+    }
+}
+
+fn pick_by_mtime(
+    group: &[PathBuf],
+    order: impl Fn(&std::time::SystemTime, &std::time::SystemTime) -> std::cmp::Ordering,
+) -> usize {
+    group
+        .iter()
+        .enumerate()
+        .filter_map(|(i, p)| std::fs::metadata(p).and_then(|m| m.modified()).ok().map(|t| (i, t)))
+        .min_by(|(_, a), (_, b)| order(a, b))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+/// Outcome of applying an action to one duplicate group.
+#[derive(Default)]
+pub struct ApplyResult {
+    pub files_removed: u64,
+    pub bytes_freed: u64,
+}
+
+/// Apply `action` to one group of duplicate files, keeping the member chosen
+/// by `keep` and replacing the rest according to `action`. Every planned
+/// change is printed; unless `confirm` is set nothing is mutated and a zeroed
+/// result is returned. `Action::Report` returns at once without printing or
+/// mutating anything.
+pub fn apply_group(
+    group: &[PathBuf],
+    action: Action,
+    keep: KeepPolicy,
+    confirm: bool,
+) -> io::Result<ApplyResult> {
+    let mut result = ApplyResult::default();
+    if action == Action::Report || group.len() < 2 {
+        return Ok(result);
+    }
+
+    let keep_idx = keep.pick(group);
+    let kept = &group[keep_idx];
+    let size = std::fs::metadata(kept).map(|m| m.len()).unwrap_or(0);
+
+    for (i, path) in group.iter().enumerate() {
+        if i == keep_idx {
+            continue;
+        }
+        println!("  {:?}: {} -> kept {}", action, path.display(), kept.display());
+        if !confirm {
+            continue;
+        }
+        match action {
+            Action::Report => unreachable!(),
+            Action::Delete => {
+                std::fs::remove_file(path)?;
+            }
+            Action::Hardlink => replace_with_link(path, kept, false)?,
+            Action::Symlink => replace_with_link(path, kept, true)?,
+        }
+        result.files_removed += 1;
+        result.bytes_freed += size;
+    }
+
+    Ok(result)
+}
+
+/// Replace `path` with a link to `kept`, writing the link to a temp name in
+/// the same directory first and renaming it over `path` so an interrupted
+/// run never leaves the original file missing.
+fn replace_with_link(path: &Path, kept: &Path, symbolic: bool) -> io::Result<()> {
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    let tmp = parent.join(format!(
+        ".{}.dedup-tmp",
+        path.file_name().and_then(|n| n.to_str()).unwrap_or("link")
+    ));
+
+    if tmp.exists() {
+        std::fs::remove_file(&tmp)?;
+    }
+    if symbolic {
+        // `kept` is whatever path the directory walk produced, which is
+        // relative when the user scanned a relative root. A relative
+        // symlink target is resolved against the link's own directory, not
+        // the cwd, so it must be made absolute or it dangles after rename.
+        let target = std::fs::canonicalize(kept)?;
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&target, &tmp)?;
+        #[cfg(not(unix))]
+        std::os::windows::fs::symlink_file(&target, &tmp)?;
+    } else {
+        std::fs::hard_link(kept, &tmp)?;
+    }
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
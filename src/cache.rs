@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::hash::HashAlgo;
+
+/// The remembered partial and/or full hash for a path, tagged with the file
+/// state they were computed from and the algorithm used.
+#[derive(Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    size: u64,
+    mtime_nanos: u64,
+    partial_hash: Option<String>,
+    full_hash: Option<String>,
+    algo: String,
+}
+
+/// Persistent cache of partial/full hashes keyed by path, so that
+/// re-scanning a mostly-static tree can skip rehashing files whose size and
+/// mtime haven't changed since the last run. Paths are stored exactly as
+/// passed in and are not made absolute, so a relative path is only matched
+/// by the same relative path on a later run.
+#[derive(Default)]
+pub struct HashCache {
+    path: PathBuf,
+    entries: HashMap<PathBuf, CacheEntry>,
+    seen: std::collections::HashSet<PathBuf>,
+    pub hits: u64,
+    pub misses: u64,
+    dirty: bool,
+}
+
+impl HashCache {
+    /// Default cache file location inside the OS cache dir.
+    pub fn default_path() -> PathBuf {
+        dirs::cache_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("dedup-rs")
+            .join("hash_cache.json")
+    }
+
+    /// Load the cache from `path`, starting empty if it doesn't exist or is
+    /// unreadable/corrupt.
+    pub fn load(path: &Path) -> Self {
+        let entries = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str::<HashMap<PathBuf, CacheEntry>>(&s).ok())
+            .unwrap_or_default();
+        HashCache {
+            path: path.to_path_buf(),
+            entries,
+            seen: std::collections::HashSet::new(),
+            hits: 0,
+            misses: 0,
+            dirty: false,
+        }
+    }
+
+    fn fresh<'a>(&'a mut self, path: &Path, size: u64, mtime_nanos: u64, algo: HashAlgo) -> Option<&'a mut CacheEntry> {
+        match self.entries.get(path) {
+            Some(e) if e.size == size && e.mtime_nanos == mtime_nanos && e.algo == algo.name() => {
+                self.entries.get_mut(path)
+            }
+            _ => None,
+        }
+    }
+
+    /// Return the cached partial hash for `path` if its size/mtime still
+    /// match what was recorded, marking the entry as seen so it survives
+    /// pruning.
+    pub fn get_partial(&mut self, path: &Path, size: u64, mtime_nanos: u64, algo: HashAlgo) -> Option<String> {
+        self.seen.insert(path.to_path_buf());
+        let found = self
+            .fresh(path, size, mtime_nanos, algo)
+            .and_then(|e| e.partial_hash.clone());
+        match found {
+            Some(h) => {
+                self.hits += 1;
+                Some(h)
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Same as [`get_partial`](Self::get_partial) but for the full-file hash.
+    pub fn get_full(&mut self, path: &Path, size: u64, mtime_nanos: u64, algo: HashAlgo) -> Option<String> {
+        self.seen.insert(path.to_path_buf());
+        let found = self
+            .fresh(path, size, mtime_nanos, algo)
+            .and_then(|e| e.full_hash.clone());
+        match found {
+            Some(h) => {
+                self.hits += 1;
+                Some(h)
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn put_partial(&mut self, path: &Path, size: u64, mtime_nanos: u64, algo: HashAlgo, hash: String) {
+        self.dirty = true;
+        let entry = self.entry_for(path, size, mtime_nanos, algo);
+        entry.partial_hash = Some(hash);
+    }
+
+    pub fn put_full(&mut self, path: &Path, size: u64, mtime_nanos: u64, algo: HashAlgo, hash: String) {
+        self.dirty = true;
+        let entry = self.entry_for(path, size, mtime_nanos, algo);
+        entry.full_hash = Some(hash);
+    }
+
+    fn entry_for(&mut self, path: &Path, size: u64, mtime_nanos: u64, algo: HashAlgo) -> &mut CacheEntry {
+        let needs_reset = match self.entries.get(path) {
+            Some(e) => e.size != size || e.mtime_nanos != mtime_nanos || e.algo != algo.name(),
+            None => true,
+        };
+        if needs_reset {
+            self.entries.insert(
+                path.to_path_buf(),
+                CacheEntry {
+                    size,
+                    mtime_nanos,
+                    partial_hash: None,
+                    full_hash: None,
+                    algo: algo.name().to_string(),
+                },
+            );
+        }
+        self.entries.get_mut(path).unwrap()
+    }
+
+    /// Drop entries for paths that weren't looked up during this run, then
+    /// write the cache back to disk if anything changed.
+    pub fn flush(mut self) -> io::Result<()> {
+        let before = self.entries.len();
+        let seen = &self.seen;
+        self.entries.retain(|p, _| seen.contains(p));
+        if self.entries.len() != before {
+            self.dirty = true;
+        }
+        if !self.dirty {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string(&self.entries).unwrap_or_default();
+        std::fs::write(&self.path, json)
+    }
+}
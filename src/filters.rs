@@ -0,0 +1,83 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::path::Path;
+
+/// Scope-narrowing filters applied while walking the scan directory, so
+/// irrelevant files never make it into the size map and are never hashed.
+#[derive(Clone)]
+pub struct ScanFilters {
+    exclude_dirs: GlobSet,
+    include_ext: Option<Vec<String>>,
+    exclude_ext: Vec<String>,
+    min_size: u64,
+    max_size: u64,
+}
+
+impl ScanFilters {
+    pub fn new(
+        exclude_dir_globs: &[String],
+        include_ext: Option<&str>,
+        exclude_ext: Option<&str>,
+        min_size: u64,
+        max_size: u64,
+    ) -> Result<Self, globset::Error> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in exclude_dir_globs {
+            builder.add(Glob::new(pattern)?);
+        }
+        Ok(ScanFilters {
+            exclude_dirs: builder.build()?,
+            include_ext: include_ext.map(split_ext_list),
+            exclude_ext: exclude_ext.map(split_ext_list).unwrap_or_default(),
+            min_size,
+            max_size,
+        })
+    }
+
+    /// Whether `path` (a directory encountered during the walk) should be
+    /// pruned, skipping its entire subtree.
+    pub fn prune_dir(&self, path: &Path) -> bool {
+        self.exclude_dirs.is_match(path)
+    }
+
+    /// Whether a regular file should be kept based on extension and size.
+    pub fn keep_file(&self, path: &Path, size: u64) -> bool {
+        if size < self.min_size || size > self.max_size {
+            return false;
+        }
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .unwrap_or_default();
+        if self.exclude_ext.contains(&ext) {
+            return false;
+        }
+        if let Some(include) = &self.include_ext {
+            return include.contains(&ext);
+        }
+        true
+    }
+}
+
+fn split_ext_list(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(|e| e.trim().trim_start_matches('.').to_ascii_lowercase())
+        .filter(|e| !e.is_empty())
+        .collect()
+}
+
+/// Parse a human-readable size like `10M`, `500K`, or a plain byte count.
+pub fn parse_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let (num_part, mult) = match s.chars().last() {
+        Some(c) if c.eq_ignore_ascii_case(&'k') => (&s[..s.len() - 1], 1024u64),
+        Some(c) if c.eq_ignore_ascii_case(&'m') => (&s[..s.len() - 1], 1024 * 1024),
+        Some(c) if c.eq_ignore_ascii_case(&'g') => (&s[..s.len() - 1], 1024 * 1024 * 1024),
+        _ => (s, 1),
+    };
+    let num: f64 = num_part
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid size: {}", s))?;
+    Ok((num * mult as f64) as u64)
+}
@@ -0,0 +1,93 @@
+use clap::ValueEnum;
+
+/// Selectable hashing backend for the partial/full hash stages.
+///
+/// BLAKE3 is cryptographically strong; xxh3 and CRC32 are much faster
+/// non-cryptographic alternatives that are a reasonable trade-off when the
+/// goal is duplicate detection over trusted local data rather than tamper
+/// resistance.
+#[derive(Copy, Clone, Debug, ValueEnum, PartialEq, Eq)]
+pub enum HashAlgo {
+    Blake3,
+    Xxh3,
+    Crc32,
+}
+
+impl HashAlgo {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashAlgo::Blake3 => "blake3",
+            HashAlgo::Xxh3 => "xxh3",
+            HashAlgo::Crc32 => "crc32",
+        }
+    }
+
+    pub fn new_hasher(&self) -> Box<dyn FileHasher> {
+        match self {
+            HashAlgo::Blake3 => Box::new(Blake3Hasher(blake3::Hasher::new())),
+            HashAlgo::Xxh3 => Box::new(Xxh3Hasher(xxhash_rust::xxh3::Xxh3::new())),
+            HashAlgo::Crc32 => Box::new(Crc32Hasher(crc32fast::Hasher::new())),
+        }
+    }
+}
+
+/// Common interface for the hash backends used by stage 2 and stage 3.
+///
+/// `finish_hex` consumes the hasher so callers can't accidentally keep
+/// feeding it bytes after reading out the digest. `current_hex` is the
+/// non-consuming counterpart used by the block-incremental stage 3, which
+/// needs the running digest after every block without losing the ability to
+/// keep hashing.
+pub trait FileHasher {
+    fn update(&mut self, bytes: &[u8]);
+    fn finish_hex(self: Box<Self>) -> String;
+    fn current_hex(&self) -> String;
+}
+
+struct Blake3Hasher(blake3::Hasher);
+
+impl FileHasher for Blake3Hasher {
+    fn update(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn finish_hex(self: Box<Self>) -> String {
+        self.0.finalize().to_hex().to_string()
+    }
+
+    fn current_hex(&self) -> String {
+        self.0.finalize().to_hex().to_string()
+    }
+}
+
+struct Xxh3Hasher(xxhash_rust::xxh3::Xxh3);
+
+impl FileHasher for Xxh3Hasher {
+    fn update(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn finish_hex(self: Box<Self>) -> String {
+        format!("{:016x}", self.0.digest())
+    }
+
+    fn current_hex(&self) -> String {
+        format!("{:016x}", self.0.digest())
+    }
+}
+
+struct Crc32Hasher(crc32fast::Hasher);
+
+impl FileHasher for Crc32Hasher {
+    fn update(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn finish_hex(self: Box<Self>) -> String {
+        format!("{:08x}", self.0.finalize())
+    }
+
+    fn current_hex(&self) -> String {
+        format!("{:08x}", self.0.clone().finalize())
+    }
+}
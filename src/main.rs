@@ -9,6 +9,17 @@ use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 use serde::Serialize;
 
+mod actions;
+mod cache;
+mod filters;
+mod hash;
+mod phash;
+
+use actions::{Action, KeepPolicy};
+use cache::HashCache;
+use filters::ScanFilters;
+use hash::HashAlgo;
+
 // 定义命令行参数
 #[derive(Parser, Debug)]
 #[command(author, version, about = "Scan a directory and report groups of duplicate files", long_about = None)]
@@ -24,6 +35,58 @@ struct Args {
     /// Output format: txt, csv, json
     #[arg(long = "format", value_enum, default_value_t = OutputFormat::Txt)]
     format: OutputFormat,
+
+    /// Hash algorithm used for the partial/full hash stages
+    #[arg(long = "hash", value_enum, default_value_t = HashAlgo::Blake3)]
+    hash: HashAlgo,
+
+    /// Path to the persistent hash cache (default: OS cache dir)
+    #[arg(long = "cache", value_name = "PATH")]
+    cache: Option<String>,
+
+    /// Disable the persistent hash cache
+    #[arg(long = "no-cache")]
+    no_cache: bool,
+
+    /// What to do with each group of confirmed duplicates
+    #[arg(long = "action", value_enum, default_value_t = Action::Report)]
+    action: Action,
+
+    /// Which file in a duplicate group to keep
+    #[arg(long = "keep", value_enum, default_value_t = KeepPolicy::First)]
+    keep: KeepPolicy,
+
+    /// Actually mutate the filesystem for --action delete/hardlink/symlink
+    #[arg(long = "confirm")]
+    confirm: bool,
+
+    /// Glob pattern for directories to skip entirely (repeatable, e.g. **/node_modules)
+    #[arg(long = "exclude-dir", value_name = "GLOB")]
+    exclude_dir: Vec<String>,
+
+    /// Comma-separated extension allow-list (case-insensitive, e.g. jpg,png)
+    #[arg(long = "include-ext", value_name = "EXTS")]
+    include_ext: Option<String>,
+
+    /// Comma-separated extension deny-list (case-insensitive)
+    #[arg(long = "exclude-ext", value_name = "EXTS")]
+    exclude_ext: Option<String>,
+
+    /// Minimum file size to consider, e.g. 10M, 500K
+    #[arg(long = "min-size", value_name = "SIZE")]
+    min_size: Option<String>,
+
+    /// Maximum file size to consider, e.g. 10M, 500K
+    #[arg(long = "max-size", value_name = "SIZE")]
+    max_size: Option<String>,
+
+    /// Instead of exact-hash dedup, group visually similar images via perceptual hashing
+    #[arg(long = "similar-images")]
+    similar_images: bool,
+
+    /// Maximum Hamming distance between dHash fingerprints to consider images similar
+    #[arg(long = "max-distance", default_value_t = 10)]
+    max_distance: u32,
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum)]
@@ -36,9 +99,35 @@ fn main() -> io::Result<()> {
     let scan_path = Path::new(&args.directory);
     let start_time = Instant::now();
 
+    let cache = if args.no_cache {
+        None
+    } else {
+        let cache_path = args
+            .cache
+            .as_ref()
+            .map(PathBuf::from)
+            .unwrap_or_else(HashCache::default_path);
+        Some(std::sync::Mutex::new(HashCache::load(&cache_path)))
+    };
+
+    let min_size = args.min_size.as_deref().map(filters::parse_size).transpose().map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?.unwrap_or(0);
+    let max_size = args.max_size.as_deref().map(filters::parse_size).transpose().map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?.unwrap_or(u64::MAX);
+    let scan_filters = ScanFilters::new(
+        &args.exclude_dir,
+        args.include_ext.as_deref(),
+        args.exclude_ext.as_deref(),
+        min_size,
+        max_size,
+    )
+    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    if args.similar_images {
+        return run_similar_images_mode(scan_path, &scan_filters, args.max_distance);
+    }
+
     println!("Stage 1: Collecting files and grouping by size...");
     let stage1_start = Instant::now();
-    let size_groups = group_by_size(scan_path);
+    let size_groups = group_by_size(scan_path, &scan_filters);
     // Metrics: total files and bytes across all files
     let total_files: u64 = size_groups.values().map(|v| v.len() as u64).sum();
     let total_bytes: u64 = size_groups.iter().map(|(sz, v)| (*sz as u64) * (v.len() as u64)).sum();
@@ -65,7 +154,7 @@ fn main() -> io::Result<()> {
             per_file * (paths.len() as u64)
         })
         .sum();
-    let partial_hash_groups = filter_by_partial_hash(potential_duplicates);
+    let partial_hash_groups = filter_by_partial_hash(potential_duplicates, args.hash, cache.as_ref());
     let partial_groups = partial_hash_groups.len() as u64;
     let time_stage2 = stage2_start.elapsed();
     println!(
@@ -75,16 +164,18 @@ fn main() -> io::Result<()> {
     );
 
     println!("\nStage 3: Confirming with full hash...");
-    // Metrics: bytes hashed fully (sum of sizes for all files entering stage 3)
-    let bytes_hashed_full: u64 = partial_hash_groups
-        .values()
-        .flatten()
-        .filter_map(|p| std::fs::metadata(p).ok())
-        .map(|m| m.len())
-        .sum();
+    // Metrics: bytes actually read by the block-incremental full hash below,
+    // which can be far less than the sum of file sizes once files diverge.
+    let bytes_hashed_full_counter = std::sync::atomic::AtomicU64::new(0);
 
     let stage3_start = Instant::now();
-    let duplicate_groups = confirm_with_full_hash(partial_hash_groups);
+    let duplicate_groups = confirm_with_full_hash(
+        partial_hash_groups,
+        args.hash,
+        cache.as_ref(),
+        &bytes_hashed_full_counter,
+    );
+    let bytes_hashed_full = bytes_hashed_full_counter.load(std::sync::atomic::Ordering::Relaxed);
     let time_stage3 = stage3_start.elapsed();
     println!(
         "Found {} groups of duplicate files. Total time: {:.2?}",
@@ -103,6 +194,19 @@ fn main() -> io::Result<()> {
         })
         .sum();
 
+    println!("\nApplying action: {:?} (keep: {:?}, confirm: {})", args.action, args.keep, args.confirm);
+    let mut files_removed = 0u64;
+    let mut bytes_freed = 0u64;
+    for group in &duplicate_groups {
+        match actions::apply_group(group, args.action, args.keep, args.confirm) {
+            Ok(r) => {
+                files_removed += r.files_removed;
+                bytes_freed += r.bytes_freed;
+            }
+            Err(e) => eprintln!("Warning: failed to apply action to group: {}", e),
+        }
+    }
+
     let metrics = Metrics {
         total_files,
         total_bytes,
@@ -117,8 +221,19 @@ fn main() -> io::Result<()> {
         time_stage2_secs: dur_secs(time_stage2),
         time_stage3_secs: dur_secs(time_stage3),
         time_total_secs: dur_secs(start_time.elapsed()),
+        hash_algo: args.hash.name(),
+        cache_hits: cache.as_ref().map(|c| c.lock().unwrap().hits).unwrap_or(0),
+        cache_misses: cache.as_ref().map(|c| c.lock().unwrap().misses).unwrap_or(0),
+        files_removed,
+        bytes_freed,
     };
 
+    if let Some(cache) = cache {
+        if let Err(e) = cache.into_inner().unwrap().flush() {
+            eprintln!("Warning: failed to write hash cache: {}", e);
+        }
+    }
+
     println!("\n--- Duplicate Files Found ---");
     if let Some(out_path) = &args.output {
         write_output(out_path, args.format, &duplicate_groups, &metrics)?;
@@ -132,14 +247,85 @@ fn main() -> io::Result<()> {
 
 fn dur_secs(d: Duration) -> f64 { d.as_secs_f64() }
 
-/// Stage 1: 遍历目录，按文件大小分组
-fn group_by_size(path: &Path) -> HashMap<u64, Vec<PathBuf>> {
+/// `--similar-images` mode: walk the tree (reusing the same directory
+/// pruning and size filters as the exact-hash pipeline), compute a dHash
+/// fingerprint per image, index them in a BK-tree, and report groups of
+/// images within `max_distance` Hamming bits of the group's first image.
+/// Two other members of one group can be more than `max_distance` apart.
+fn run_similar_images_mode(path: &Path, filters: &ScanFilters, max_distance: u32) -> io::Result<()> {
+    use rayon::iter::ParallelBridge;
+
+    println!("Scanning for images...");
+    let dir_filters = filters.clone();
+    let image_paths: Vec<PathBuf> = WalkDir::new(path)
+        .process_read_dir(move |_depth, _path, _state, children| {
+            children.retain(|entry| match entry {
+                Ok(e) if e.file_type().is_dir() => !dir_filters.prune_dir(&e.path()),
+                _ => true,
+            });
+        })
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.path())
+        .filter(|p| phash::is_image(p))
+        .filter(|p| {
+            std::fs::metadata(p)
+                .map(|m| filters.keep_file(p, m.len()))
+                .unwrap_or(false)
+        })
+        .collect();
+
+    println!("Computing perceptual hashes for {} images...", image_paths.len());
+    let bar = ProgressBar::new(image_paths.len() as u64);
+    let fingerprints: Vec<(u64, PathBuf)> = image_paths
+        .into_iter()
+        .par_bridge()
+        .filter_map(|p| {
+            bar.inc(1);
+            phash::dhash(&p).map(|h| (h, p))
+        })
+        .collect();
+    bar.finish_with_message("Done hashing.");
+
+    let mut tree = phash::BkTree::new();
+    for (hash, path) in fingerprints {
+        tree.insert(hash, path);
+    }
+
+    let groups = phash::group_similar(&tree, max_distance);
+
+    println!("\n--- Similar Image Groups (max distance {}) ---", max_distance);
+    if groups.is_empty() {
+        println!("No similar images found.");
+    } else {
+        for (i, group) in groups.iter().enumerate() {
+            println!("\nGroup {}:", i + 1);
+            for (path, dist) in group.paths.iter().zip(&group.distances) {
+                println!("  - {} (distance {})", path.display(), dist);
+            }
+        }
+    }
+    println!("\nsimilar_groups: {}", groups.len());
+
+    Ok(())
+}
+
+/// Stage 1: 遍历目录，按文件大小分组（跳过被 `filters` 排除的目录/文件）
+fn group_by_size(path: &Path, filters: &ScanFilters) -> HashMap<u64, Vec<PathBuf>> {
     use rayon::iter::ParallelBridge;
     let spinner = ProgressBar::new_spinner();
     spinner.set_message("Scanning files (parallel)...");
 
     // jwalk yields entries (iterator). Use par_bridge to process entries in parallel.
+    let dir_filters = filters.clone();
     let entries: Vec<(u64, PathBuf)> = WalkDir::new(path)
+        .process_read_dir(move |_depth, _path, _state, children| {
+            children.retain(|entry| match entry {
+                Ok(e) if e.file_type().is_dir() => !dir_filters.prune_dir(&e.path()),
+                _ => true,
+            });
+        })
         .into_iter()
         .par_bridge()
         .filter_map(|e| e.ok())
@@ -150,7 +336,7 @@ fn group_by_size(path: &Path) -> HashMap<u64, Vec<PathBuf>> {
                 std::fs::metadata(e.path()).map_err(|_| ())
             });
             match meta_res {
-                Ok(md) if md.len() > 0 => Some((md.len(), e.path())),
+                Ok(md) if md.len() > 0 && filters.keep_file(&e.path(), md.len()) => Some((md.len(), e.path())),
                 _ => None,
             }
         })
@@ -168,6 +354,8 @@ fn group_by_size(path: &Path) -> HashMap<u64, Vec<PathBuf>> {
 /// Stage 2: 对大小相同的文件组计算部分哈希值
 fn filter_by_partial_hash(
     size_groups: HashMap<u64, Vec<PathBuf>>,
+    algo: HashAlgo,
+    cache: Option<&std::sync::Mutex<HashCache>>,
 ) -> HashMap<String, Vec<PathBuf>> {
     let total: u64 = size_groups.values().map(|v| v.len() as u64).sum();
     let bar = ProgressBar::new(total);
@@ -180,11 +368,11 @@ fn filter_by_partial_hash(
     // 计算 (partial_hash, path) 对，然后按 hash 分组
     let pairs: Vec<(String, PathBuf)> = size_groups
         .into_par_iter()
-        .flat_map(|(_, paths)| {
+        .flat_map(|(size, paths)| {
             let mut v = Vec::with_capacity(paths.len());
             for path in paths {
                 bar.inc(1);
-                if let Ok(hash) = calculate_partial_hash(&path) {
+                if let Ok(hash) = partial_hash_cached(&path, size, algo, cache) {
                     v.push((hash, path));
                 }
             }
@@ -203,9 +391,25 @@ fn filter_by_partial_hash(
     hash_groups
 }
 
-/// Stage 3: 对部分哈希值也相同的文件组计算完整哈希值
+/// Size of each block read while incrementally confirming a partial-hash
+/// group in stage 3.
+const FULL_HASH_BLOCK_SIZE: usize = 1024 * 1024;
+
+/// Stage 3: 对部分哈希值也相同的文件组，分块增量计算完整哈希并提前剔除不匹配的文件
+///
+/// Rather than reading every file in a group to completion, each member is
+/// hashed one `FULL_HASH_BLOCK_SIZE` block at a time. After every block the
+/// running (cumulative) digests are compared; any file whose digest no
+/// longer matches at least one other live candidate is dropped before the
+/// rest of it is ever read. A group whose survivors diverge completely stops
+/// early without reading the remaining bytes. A lone live candidate (the
+/// other members resolved from the cache) is read in full so its hash can be
+/// matched against the cached ones.
 fn confirm_with_full_hash(
     partial_hash_groups: HashMap<String, Vec<PathBuf>>,
+    algo: HashAlgo,
+    cache: Option<&std::sync::Mutex<HashCache>>,
+    bytes_hashed_full: &std::sync::atomic::AtomicU64,
 ) -> Vec<Vec<PathBuf>> {
     let total: u64 = partial_hash_groups.values().map(|v| v.len() as u64).sum();
     let bar = ProgressBar::new(total);
@@ -215,20 +419,13 @@ fn confirm_with_full_hash(
             .unwrap(),
     );
 
-    // 将所有路径拍平后并行计算完整哈希
-    let all_paths: Vec<PathBuf> = partial_hash_groups
-        .into_values()
-        .flatten()
-        .collect();
-
-    let pairs: Vec<(String, PathBuf)> = all_paths
+    let pairs: Vec<(String, PathBuf)> = partial_hash_groups
         .into_par_iter()
-        .filter_map(|path| {
-            bar.inc(1);
-            match calculate_full_hash(&path) {
-                Ok(h) => Some((h, path)),
-                Err(_) => None,
-            }
+        .flat_map(|(_, paths)| {
+            let n = paths.len() as u64;
+            let result = confirm_group_incrementally(paths, algo, cache, bytes_hashed_full);
+            bar.inc(n);
+            result
         })
         .collect();
 
@@ -246,8 +443,212 @@ fn confirm_with_full_hash(
     duplicate_groups
 }
 
-/// 计算文件前 `PARTIAL_HASH_SIZE` 字节的 BLAKE3 哈希
-fn calculate_partial_hash(path: &Path) -> io::Result<String> {
+/// One file still being compared within a stage-3 group.
+struct LiveCandidate {
+    path: PathBuf,
+    size: u64,
+    mtime: u64,
+    file: File,
+    hasher: Box<dyn hash::FileHasher>,
+    at_eof: bool,
+    errored: bool,
+}
+
+/// Confirm one partial-hash group by reading all members in lockstep
+/// blocks, dropping files whose running digest no longer matches any other
+/// live candidate. Returns the subset of `paths` whose full-file hash was
+/// actually confirmed (either from the cache or by reading to EOF), paired
+/// with that hash; newly computed hashes are written back to `cache`.
+fn confirm_group_incrementally(
+    paths: Vec<PathBuf>,
+    algo: HashAlgo,
+    cache: Option<&std::sync::Mutex<HashCache>>,
+    bytes_hashed_full: &std::sync::atomic::AtomicU64,
+) -> Vec<(String, PathBuf)> {
+    let mut confirmed: Vec<(String, PathBuf)> = Vec::new();
+    let mut live: Vec<LiveCandidate> = Vec::new();
+
+    for path in paths {
+        let (size, mtime) = match (std::fs::metadata(&path).map(|m| m.len()), mtime_nanos(&path)) {
+            (Ok(size), Ok(mtime)) => (size, mtime),
+            _ => continue,
+        };
+        if let Some(cache) = cache {
+            if let Some(h) = cache.lock().unwrap().get_full(&path, size, mtime, algo) {
+                confirmed.push((h, path));
+                continue;
+            }
+        }
+        match File::open(&path) {
+            Ok(file) => live.push(LiveCandidate {
+                path,
+                size,
+                mtime,
+                file,
+                hasher: algo.new_hasher(),
+                at_eof: false,
+                errored: false,
+            }),
+            Err(_) => continue,
+        }
+    }
+
+    let mut buf = vec![0u8; FULL_HASH_BLOCK_SIZE];
+    while live.len() > 1 {
+        for candidate in live.iter_mut() {
+            if candidate.at_eof || candidate.errored {
+                continue;
+            }
+            match read_full_block(&mut candidate.file, &mut buf) {
+                Ok(read) => {
+                    if read > 0 {
+                        candidate.hasher.update(&buf[..read]);
+                        bytes_hashed_full.fetch_add(read as u64, std::sync::atomic::Ordering::Relaxed);
+                    }
+                    if read < buf.len() {
+                        candidate.at_eof = true;
+                    }
+                }
+                // A genuine I/O error (as opposed to a clean EOF) must not be
+                // treated as if the file ended there, or a truncated read
+                // could be mistaken for a confirmed match. Drop the file
+                // from this round's comparison instead.
+                Err(_) => candidate.errored = true,
+            }
+        }
+
+        let mut by_digest: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, candidate) in live.iter().enumerate() {
+            if candidate.errored {
+                continue;
+            }
+            by_digest.entry(candidate.hasher.current_hex()).or_default().push(i);
+        }
+        let keep: std::collections::HashSet<usize> = by_digest
+            .values()
+            .filter(|idxs| idxs.len() > 1)
+            .flatten()
+            .copied()
+            .collect();
+
+        if keep.is_empty() {
+            live.clear();
+            break;
+        }
+
+        let all_done = keep.iter().all(|i| live[*i].at_eof);
+        let mut kept_idx = 0;
+        live.retain(|_| {
+            let was_kept = keep.contains(&kept_idx);
+            kept_idx += 1;
+            was_kept
+        });
+
+        if all_done {
+            for candidate in live {
+                let digest = candidate.hasher.current_hex();
+                if let Some(cache) = cache {
+                    cache.lock().unwrap().put_full(
+                        &candidate.path,
+                        candidate.size,
+                        candidate.mtime,
+                        algo,
+                        digest.clone(),
+                    );
+                }
+                confirmed.push((digest, candidate.path));
+            }
+            live = Vec::new();
+            break;
+        }
+    }
+
+    // A single remaining candidate can't be matched against anything still
+    // live, but it may still duplicate a member that already resolved via
+    // the cache (those went straight to `confirmed` and never entered this
+    // loop), so it still needs its full hash to be regrouped against them.
+    if let Some(mut candidate) = live.into_iter().next() {
+        while !candidate.at_eof && !candidate.errored {
+            match read_full_block(&mut candidate.file, &mut buf) {
+                Ok(read) => {
+                    if read > 0 {
+                        candidate.hasher.update(&buf[..read]);
+                        bytes_hashed_full.fetch_add(read as u64, std::sync::atomic::Ordering::Relaxed);
+                    }
+                    if read < buf.len() {
+                        candidate.at_eof = true;
+                    }
+                }
+                Err(_) => candidate.errored = true,
+            }
+        }
+        if !candidate.errored {
+            let digest = candidate.hasher.current_hex();
+            if let Some(cache) = cache {
+                cache.lock().unwrap().put_full(
+                    &candidate.path,
+                    candidate.size,
+                    candidate.mtime,
+                    algo,
+                    digest.clone(),
+                );
+            }
+            confirmed.push((digest, candidate.path));
+        }
+    }
+
+    confirmed
+}
+
+/// Read from `file` until `buf` is full or EOF is reached, returning the
+/// number of bytes actually read (less than `buf.len()` only at true EOF).
+fn read_full_block(file: &mut File, buf: &mut [u8]) -> io::Result<usize> {
+    let mut total = 0;
+    while total < buf.len() {
+        match file.read(&mut buf[total..])? {
+            0 => break,
+            n => total += n,
+        }
+    }
+    Ok(total)
+}
+
+/// Modification time of `path` in nanoseconds since the Unix epoch, used as
+/// the cache-invalidation key alongside file size.
+fn mtime_nanos(path: &Path) -> io::Result<u64> {
+    let mtime = std::fs::metadata(path)?.modified()?;
+    Ok(mtime
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64)
+}
+
+/// Partial hash for `path`, served from `cache` when the (size, mtime) pair
+/// still matches, otherwise computed and written back.
+fn partial_hash_cached(
+    path: &Path,
+    size: u64,
+    algo: HashAlgo,
+    cache: Option<&std::sync::Mutex<HashCache>>,
+) -> io::Result<String> {
+    let mtime = mtime_nanos(path)?;
+    if let Some(cache) = cache {
+        if let Some(hash) = cache.lock().unwrap().get_partial(path, size, mtime, algo) {
+            return Ok(hash);
+        }
+    }
+    let hash = calculate_partial_hash(path, algo)?;
+    if let Some(cache) = cache {
+        cache
+            .lock()
+            .unwrap()
+            .put_partial(path, size, mtime, algo, hash.clone());
+    }
+    Ok(hash)
+}
+
+/// 计算文件头尾各 `PARTIAL_HASH_SIZE` 字节的哈希（算法由 `algo` 指定）
+fn calculate_partial_hash(path: &Path, algo: HashAlgo) -> io::Result<String> {
     let mut file = File::open(path)?;
     let metadata = file.metadata()?;
     let len = metadata.len();
@@ -273,27 +674,10 @@ fn calculate_partial_hash(path: &Path) -> io::Result<String> {
     }
 
     // Combine head and tail into one hash
-    let mut hasher = blake3::Hasher::new();
+    let mut hasher = algo.new_hasher();
     hasher.update(&head_buf);
     hasher.update(&tail_buf);
-    Ok(hasher.finalize().to_hex().to_string())
-}
-
-/// 计算整个文件的 BLAKE3 哈希
-fn calculate_full_hash(path: &Path) -> io::Result<String> {
-    let file = File::open(path)?;
-    let mut reader = BufReader::new(file);
-    let mut hasher = blake3::Hasher::new();
-    let mut buffer = [0; 65536]; // 64KB buffer
-
-    loop {
-        let bytes_read = reader.read(&mut buffer)?;
-        if bytes_read == 0 {
-            break;
-        }
-        hasher.update(&buffer[..bytes_read]);
-    }
-    Ok(hasher.finalize().to_hex().to_string())
+    Ok(hasher.finish_hex())
 }
 
 #[derive(Serialize)]
@@ -317,6 +701,11 @@ struct Metrics {
     time_stage2_secs: f64,
     time_stage3_secs: f64,
     time_total_secs: f64,
+    hash_algo: &'static str,
+    cache_hits: u64,
+    cache_misses: u64,
+    files_removed: u64,
+    bytes_freed: u64,
 }
 
 #[derive(Serialize)]
@@ -444,6 +833,11 @@ fn print_metrics_txt(m: &Metrics) {
     println!("time_stage2_secs: {:.3}", m.time_stage2_secs);
     println!("time_stage3_secs: {:.3}", m.time_stage3_secs);
     println!("time_total_secs: {:.3}", m.time_total_secs);
+    println!("hash_algo: {}", m.hash_algo);
+    println!("cache_hits: {}", m.cache_hits);
+    println!("cache_misses: {}", m.cache_misses);
+    println!("files_removed: {}", m.files_removed);
+    println!("bytes_freed: {}", m.bytes_freed);
 }
 
 fn write_metrics_txt(w: &mut impl Write, m: &Metrics) -> io::Result<()> {
@@ -460,6 +854,11 @@ fn write_metrics_txt(w: &mut impl Write, m: &Metrics) -> io::Result<()> {
     writeln!(w, "time_stage2_secs: {:.3}", m.time_stage2_secs)?;
     writeln!(w, "time_stage3_secs: {:.3}", m.time_stage3_secs)?;
     writeln!(w, "time_total_secs: {:.3}", m.time_total_secs)?;
+    writeln!(w, "hash_algo: {}", m.hash_algo)?;
+    writeln!(w, "cache_hits: {}", m.cache_hits)?;
+    writeln!(w, "cache_misses: {}", m.cache_misses)?;
+    writeln!(w, "files_removed: {}", m.files_removed)?;
+    writeln!(w, "bytes_freed: {}", m.bytes_freed)?;
     Ok(())
 }
 
@@ -478,5 +877,10 @@ fn metrics_kv(m: &Metrics) -> Vec<(&'static str, String)> {
         ("time_stage2_secs", format!("{:.3}", m.time_stage2_secs)),
         ("time_stage3_secs", format!("{:.3}", m.time_stage3_secs)),
         ("time_total_secs", format!("{:.3}", m.time_total_secs)),
+        ("hash_algo", m.hash_algo.to_string()),
+        ("cache_hits", m.cache_hits.to_string()),
+        ("cache_misses", m.cache_misses.to_string()),
+        ("files_removed", m.files_removed.to_string()),
+        ("bytes_freed", m.bytes_freed.to_string()),
     ]
 }
@@ -0,0 +1,173 @@
+use image::imageops::FilterType;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const IMAGE_EXTS: &[&str] = &["jpg", "jpeg", "png", "gif", "bmp", "webp", "tiff", "tif"];
+
+pub fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| IMAGE_EXTS.contains(&e.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// 64-bit difference hash (dHash): downscale to 9x8 grayscale and set each
+/// bit to 1 where a pixel is brighter than its right neighbour.
+pub fn dhash(path: &Path) -> Option<u64> {
+    let img = image::open(path).ok()?;
+    let small = img.resize_exact(9, 8, FilterType::Triangle).into_luma8();
+    let mut hash = 0u64;
+    let mut bit = 0;
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = small.get_pixel(x, y)[0];
+            let right = small.get_pixel(x + 1, y)[0];
+            if left > right {
+                hash |= 1 << bit;
+            }
+            bit += 1;
+        }
+    }
+    Some(hash)
+}
+
+/// BK-tree over 64-bit fingerprints, indexed by Hamming distance so that
+/// "find everything within N bits of this hash" is sublinear instead of an
+/// all-pairs scan.
+#[derive(Default)]
+pub struct BkTree {
+    root: Option<Box<Node>>,
+}
+
+struct Node {
+    hash: u64,
+    paths: Vec<PathBuf>,
+    children: HashMap<u32, Box<Node>>,
+}
+
+impl BkTree {
+    pub fn new() -> Self {
+        BkTree { root: None }
+    }
+
+    pub fn insert(&mut self, hash: u64, path: PathBuf) {
+        match &mut self.root {
+            None => {
+                self.root = Some(Box::new(Node {
+                    hash,
+                    paths: vec![path],
+                    children: HashMap::new(),
+                }));
+            }
+            Some(root) => Self::insert_at(root, hash, path),
+        }
+    }
+
+    fn insert_at(node: &mut Node, hash: u64, path: PathBuf) {
+        let dist = hamming(node.hash, hash);
+        if dist == 0 {
+            node.paths.push(path);
+            return;
+        }
+        match node.children.get_mut(&dist) {
+            Some(child) => Self::insert_at(child, hash, path),
+            None => {
+                node.children.insert(
+                    dist,
+                    Box::new(Node {
+                        hash,
+                        paths: vec![path],
+                        children: HashMap::new(),
+                    }),
+                );
+            }
+        }
+    }
+
+    /// All (hash, paths, distance) entries within `max_distance` bits of `hash`.
+    pub fn find_within(&self, hash: u64, max_distance: u32) -> Vec<(u64, &[PathBuf], u32)> {
+        let mut out = Vec::new();
+        if let Some(root) = &self.root {
+            Self::search(root, hash, max_distance, &mut out);
+        }
+        out
+    }
+
+    fn search<'a>(node: &'a Node, hash: u64, max_distance: u32, out: &mut Vec<(u64, &'a [PathBuf], u32)>) {
+        let dist = hamming(node.hash, hash);
+        if dist <= max_distance {
+            out.push((node.hash, &node.paths, dist));
+        }
+        let lo = dist.saturating_sub(max_distance);
+        let hi = dist + max_distance;
+        for (edge, child) in &node.children {
+            if *edge >= lo && *edge <= hi {
+                Self::search(child, hash, max_distance, out);
+            }
+        }
+    }
+
+    /// All fingerprints stored in the tree, used to drive grouping.
+    pub fn all_hashes(&self) -> Vec<u64> {
+        let mut out = Vec::new();
+        if let Some(root) = &self.root {
+            Self::collect(root, &mut out);
+        }
+        out
+    }
+
+    fn collect(node: &Node, out: &mut Vec<u64>) {
+        out.push(node.hash);
+        for child in node.children.values() {
+            Self::collect(child, out);
+        }
+    }
+}
+
+pub fn hamming(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// One group of visually similar images, with the pairwise distance of each
+/// member from the group's first (representative) image.
+pub struct SimilarityGroup {
+    pub paths: Vec<PathBuf>,
+    pub distances: Vec<u32>,
+}
+
+/// Group every indexed fingerprint with its neighbours within `max_distance`,
+/// visiting each fingerprint at most once so groups don't overlap: a
+/// fingerprint already claimed by an earlier seed's group is skipped when it
+/// turns up as a neighbour of a later seed, rather than being added again.
+pub fn group_similar(tree: &BkTree, max_distance: u32) -> Vec<SimilarityGroup> {
+    let mut visited = std::collections::HashSet::new();
+    let mut groups = Vec::new();
+
+    for hash in tree.all_hashes() {
+        if visited.contains(&hash) {
+            continue;
+        }
+        let mut neighbours: Vec<_> = tree
+            .find_within(hash, max_distance)
+            .into_iter()
+            .filter(|(h, _, _)| !visited.contains(h))
+            .collect();
+        // The seed itself is the only neighbour at distance 0, so it leads.
+        neighbours.sort_by_key(|(_, _, d)| *d);
+        let total_paths: usize = neighbours.iter().map(|(_, ps, _)| ps.len()).sum();
+        if total_paths < 2 {
+            continue;
+        }
+        let mut paths = Vec::new();
+        let mut distances = Vec::new();
+        for (h, ps, dist) in &neighbours {
+            visited.insert(*h);
+            for p in ps.iter() {
+                paths.push(p.clone());
+                distances.push(*dist);
+            }
+        }
+        groups.push(SimilarityGroup { paths, distances });
+    }
+    groups
+}